@@ -0,0 +1,128 @@
+use anyhow::Result;
+use futures::{SinkExt, Stream, StreamExt};
+use tokio::{
+    io::split,
+    net::ToSocketAddrs,
+    spawn,
+    sync::mpsc,
+};
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tokio_util::codec::{FramedRead, FramedWrite};
+use tracing::error;
+
+use crate::codec::EventCodec;
+use crate::server::{topic_matches, Event};
+use crate::transport::{Reconnectable, TcpTransport};
+
+/// A subscriber's interest in a topic, registered with the connection task so
+/// incoming events can be routed to the right `subscribe` stream.
+struct Subscription {
+    topic: String,
+    tx: mpsc::UnboundedSender<Event>,
+}
+
+/// A connection to a `simplebus` broker for publishing and subscribing to
+/// events, built on the same [`EventCodec`] framing the server speaks.
+pub struct Client {
+    publish_tx: mpsc::UnboundedSender<Event>,
+    subscribe_tx: mpsc::UnboundedSender<Subscription>,
+}
+
+fn subscribe_frame(topic: &str) -> Event {
+    Event {
+        topic: format!("SUBSCRIBE {}", topic),
+        payload: Vec::new(),
+    }
+}
+
+impl Client {
+    /// Connects to a broker at `addr` and spawns the background task that
+    /// drives the connection: it reads incoming frames and routes delivered
+    /// events to subscriber streams, while draining outbound publish/subscribe
+    /// requests sent over internal channels. On a read error or closed
+    /// connection the task reconnects via [`Reconnectable`] and re-sends every
+    /// active subscription, instead of silently dropping the client.
+    pub async fn connect<A: ToSocketAddrs>(addr: A) -> Result<Client> {
+        let transport = TcpTransport::connect(addr).await?;
+        let (read_half, write_half) = split(transport);
+        let mut reader = FramedRead::new(read_half, EventCodec::new());
+        let mut writer = FramedWrite::new(write_half, EventCodec::new());
+
+        let (publish_tx, mut publish_rx) = mpsc::unbounded_channel::<Event>();
+        let (subscribe_tx, mut subscribe_rx) = mpsc::unbounded_channel::<Subscription>();
+
+        spawn(async move {
+            let mut subscriptions: Vec<Subscription> = Vec::new();
+            loop {
+                tokio::select! {
+                    event = publish_rx.recv() => {
+                        let Some(event) = event else { break };
+                        if let Err(err) = writer.send(&event).await {
+                            error!("Error publishing event: {}", err);
+                            break;
+                        }
+                    }
+                    sub = subscribe_rx.recv() => {
+                        let Some(sub) = sub else { break };
+                        if let Err(err) = writer.send(&subscribe_frame(&sub.topic)).await {
+                            error!("Error sending subscription: {}", err);
+                            break;
+                        }
+                        subscriptions.push(sub);
+                    }
+                    incoming = reader.next() => {
+                        match incoming {
+                            Some(Ok(event)) => {
+                                subscriptions.retain(|sub| {
+                                    !topic_matches(&sub.topic, &event.topic)
+                                        || sub.tx.send(event.clone()).is_ok()
+                                });
+                                continue;
+                            }
+                            Some(Err(err)) => error!("Lost connection to broker: {}", err),
+                            None => error!("Broker closed the connection"),
+                        }
+
+                        let mut transport = reader.into_inner().unsplit(writer.into_inner());
+                        if let Err(err) = transport.reconnect().await {
+                            error!("Giving up after reconnect failed: {}", err);
+                            break;
+                        }
+                        let (read_half, write_half) = split(transport);
+                        reader = FramedRead::new(read_half, EventCodec::new());
+                        writer = FramedWrite::new(write_half, EventCodec::new());
+                        for sub in &subscriptions {
+                            if let Err(err) = writer.send(&subscribe_frame(&sub.topic)).await {
+                                error!("Error re-subscribing to {}: {}", sub.topic, err);
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(Client {
+            publish_tx,
+            subscribe_tx,
+        })
+    }
+
+    /// Publishes `payload` to `topic`.
+    pub fn publish(&self, topic: impl Into<String>, payload: impl Into<Vec<u8>>) -> Result<()> {
+        self.publish_tx.send(Event {
+            topic: topic.into(),
+            payload: payload.into(),
+        })?;
+        Ok(())
+    }
+
+    /// Subscribes to `topic`, returning a stream of events delivered for it.
+    pub fn subscribe(&self, topic: impl Into<String>) -> impl Stream<Item = Event> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let _ = self.subscribe_tx.send(Subscription {
+            topic: topic.into(),
+            tx,
+        });
+        UnboundedReceiverStream::new(rx)
+    }
+}
@@ -0,0 +1,218 @@
+use bytes::{Buf, BufMut, BytesMut};
+use std::io;
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::server::Event;
+
+/// Default cap on each length a frame declares (the topic's and the
+/// payload's, checked separately), guarding against a malformed or
+/// malicious peer claiming an unbounded topic/payload size.
+const DEFAULT_MAX_FRAME_LEN: usize = 16 * 1024 * 1024;
+
+/// Frames `Event`s on the wire as `varint(topic_len) || topic || varint(payload_len) || payload`.
+///
+/// Varints are LEB128: each byte carries 7 payload bits, little-endian group
+/// order, with the high bit set on every byte but the last.
+pub struct EventCodec {
+    max_frame_len: usize,
+}
+
+impl EventCodec {
+    pub fn new() -> EventCodec {
+        EventCodec {
+            max_frame_len: DEFAULT_MAX_FRAME_LEN,
+        }
+    }
+
+    pub fn with_max_frame_len(max_frame_len: usize) -> EventCodec {
+        EventCodec { max_frame_len }
+    }
+}
+
+impl Default for EventCodec {
+    fn default() -> EventCodec {
+        EventCodec::new()
+    }
+}
+
+/// A u64 LEB128 varint needs at most 10 bytes (7 payload bits per byte); a
+/// peer that keeps setting the continuation bit past that is malformed.
+const MAX_VARINT_LEN: usize = 10;
+
+/// Reads a varint from the front of `buf` without consuming it, returning the
+/// decoded value and the number of bytes it occupied, `None` if `buf`
+/// doesn't yet hold a complete varint, or an error if it never terminates
+/// within `MAX_VARINT_LEN` bytes.
+fn peek_varint(buf: &[u8]) -> io::Result<Option<(u64, usize)>> {
+    let mut value: u64 = 0;
+    for (i, &byte) in buf.iter().take(MAX_VARINT_LEN).enumerate() {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok(Some((value, i + 1)));
+        }
+    }
+    if buf.len() >= MAX_VARINT_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("varint longer than {} bytes", MAX_VARINT_LEN),
+        ));
+    }
+    Ok(None)
+}
+
+fn put_varint(buf: &mut BytesMut, mut value: u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf.put_u8(byte);
+            break;
+        }
+        buf.put_u8(byte | 0x80);
+    }
+}
+
+/// Attempts to peek a length-prefixed field starting at `offset`, returning
+/// the field's byte range (relative to `buf`) and where the next field
+/// starts, or `None` if `buf` doesn't yet hold the full field.
+fn peek_field(buf: &[u8], offset: usize, max_len: usize) -> io::Result<Option<(usize, usize)>> {
+    let (len, varint_len) = match peek_varint(&buf[offset..])? {
+        Some(v) => v,
+        None => return Ok(None),
+    };
+    if len as usize > max_len {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("frame length {} exceeds max_frame_len {}", len, max_len),
+        ));
+    }
+    let field_start = offset + varint_len;
+    let field_end = field_start + len as usize;
+    if buf.len() < field_end {
+        return Ok(None);
+    }
+    Ok(Some((field_start, field_end)))
+}
+
+impl Decoder for EventCodec {
+    type Item = Event;
+    type Error = io::Error;
+
+    fn decode(&mut self, buf: &mut BytesMut) -> io::Result<Option<Event>> {
+        let (topic_start, topic_end) = match peek_field(buf, 0, self.max_frame_len)? {
+            Some(range) => range,
+            None => return Ok(None),
+        };
+        let (payload_start, payload_end) =
+            match peek_field(buf, topic_end, self.max_frame_len)? {
+                Some(range) => range,
+                None => return Ok(None),
+            };
+
+        let topic = std::str::from_utf8(&buf[topic_start..topic_end])
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?
+            .to_string();
+        let payload = buf[payload_start..payload_end].to_vec();
+
+        buf.advance(payload_end);
+        Ok(Some(Event { topic, payload }))
+    }
+}
+
+impl Encoder<Event> for EventCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, event: Event, buf: &mut BytesMut) -> io::Result<()> {
+        put_varint(buf, event.topic.len() as u64);
+        buf.put_slice(event.topic.as_bytes());
+        put_varint(buf, event.payload.len() as u64);
+        buf.put_slice(&event.payload);
+        Ok(())
+    }
+}
+
+impl Encoder<&Event> for EventCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, event: &Event, buf: &mut BytesMut) -> io::Result<()> {
+        put_varint(buf, event.topic.len() as u64);
+        buf.put_slice(event.topic.as_bytes());
+        put_varint(buf, event.payload.len() as u64);
+        buf.put_slice(&event.payload);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_an_event() {
+        let mut codec = EventCodec::new();
+        let mut buf = BytesMut::new();
+        let event = Event {
+            topic: "orders.created".to_string(),
+            payload: b"hello".to_vec(),
+        };
+        codec.encode(&event, &mut buf).unwrap();
+
+        let decoded = codec.decode(&mut buf).unwrap().unwrap();
+        assert_eq!(decoded.topic, "orders.created");
+        assert_eq!(decoded.payload, b"hello");
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn decode_returns_none_on_a_partial_frame() {
+        let mut codec = EventCodec::new();
+        let mut buf = BytesMut::new();
+        codec
+            .encode(
+                &Event {
+                    topic: "orders.created".to_string(),
+                    payload: b"hello".to_vec(),
+                },
+                &mut buf,
+            )
+            .unwrap();
+
+        // Split the encoded frame so only the first byte has arrived so far.
+        let rest = buf.split_off(1);
+        assert!(codec.decode(&mut buf).unwrap().is_none());
+
+        // Once the remaining bytes arrive, the frame decodes whole.
+        buf.unsplit(rest);
+        let decoded = codec.decode(&mut buf).unwrap().unwrap();
+        assert_eq!(decoded.topic, "orders.created");
+        assert_eq!(decoded.payload, b"hello");
+    }
+
+    #[test]
+    fn rejects_a_varint_that_never_terminates() {
+        let mut codec = EventCodec::new();
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(&[0xFF; 11]);
+
+        let err = codec.decode(&mut buf).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn rejects_a_frame_longer_than_max_frame_len() {
+        let mut codec = EventCodec::with_max_frame_len(4);
+        let mut buf = BytesMut::new();
+        codec
+            .encode(
+                &Event {
+                    topic: "a-topic-longer-than-four-bytes".to_string(),
+                    payload: Vec::new(),
+                },
+                &mut buf,
+            )
+            .unwrap();
+
+        let err = codec.decode(&mut buf).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,7 @@
+pub mod client;
+pub mod codec;
+pub mod server;
+pub mod transport;
+
+pub use client::Client;
+pub use server::{Event, Server};
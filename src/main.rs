@@ -1,6 +1,5 @@
-use crate::server::Server;
+use simplebus::Server;
 use tracing::error;
-mod server;
 
 #[tokio::main]
 async fn main() {
@@ -1,62 +1,250 @@
 use anyhow::Result;
+use futures::{SinkExt, StreamExt};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use tokio::{
-    io::{AsyncBufReadExt, AsyncReadExt, BufReader},
-    net::{TcpListener, TcpStream},
+    io::{split, ReadHalf, WriteHalf},
+    net::{TcpListener, ToSocketAddrs},
     spawn,
+    sync::{mpsc, Mutex, Notify, Semaphore},
+    task::JoinSet,
 };
+use tokio_rustls::TlsAcceptor;
+use tokio_util::codec::{FramedRead, FramedWrite};
 use tracing::{error, info};
 
+use crate::codec::EventCodec;
+use crate::transport::{Transport, TlsTransport};
+
+#[derive(Clone)]
 pub struct Event {
-    topic: String,
-    payload: Vec<u8>,
+    pub topic: String,
+    pub payload: Vec<u8>,
+}
+
+enum Frame {
+    Subscribe(String),
+    Publish(Event),
+}
+
+impl From<Event> for Frame {
+    fn from(event: Event) -> Frame {
+        match event.topic.strip_prefix("SUBSCRIBE ") {
+            Some(topic) => Frame::Subscribe(topic.to_string()),
+            None => Frame::Publish(event),
+        }
+    }
 }
-pub struct Server {
-    pub address: String,
+
+/// Registered subscribers, keyed by the exact topic (or pattern) they
+/// subscribed to; each entry is tagged with a subscription id so a
+/// disconnecting connection can remove exactly its own registration.
+type Subscribers = HashMap<String, Vec<(u64, mpsc::UnboundedSender<Arc<Event>>)>>;
+
+/// State shared across every connection handler: the subscriber registry.
+struct Shared {
+    subscribers: Mutex<Subscribers>,
+    next_subscription_id: AtomicU64,
 }
 
-impl Server {
-    pub fn new(address: &str) -> Server {
+impl Shared {
+    fn new() -> Shared {
+        Shared {
+            subscribers: Mutex::new(HashMap::new()),
+            next_subscription_id: AtomicU64::new(0),
+        }
+    }
+
+    async fn subscribe(&self, topic: String) -> (u64, mpsc::UnboundedReceiver<Arc<Event>>) {
+        let id = self.next_subscription_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.subscribers
+            .lock()
+            .await
+            .entry(topic)
+            .or_default()
+            .push((id, tx));
+        (id, rx)
+    }
+
+    /// Removes a single subscription immediately, so a disconnected
+    /// connection's forwarder doesn't linger in the registry waiting for a
+    /// future publish to prune it.
+    async fn unsubscribe(&self, topic: &str, id: u64) {
+        let mut subscribers = self.subscribers.lock().await;
+        if let Some(senders) = subscribers.get_mut(topic) {
+            senders.retain(|(sub_id, _)| *sub_id != id);
+            if senders.is_empty() {
+                subscribers.remove(topic);
+            }
+        }
+    }
+
+    /// Fan the event out to every subscriber whose topic matches, exactly or via
+    /// a trailing `*` wildcard (e.g. a subscription to `"orders.*"` matches a
+    /// publish to `"orders.created"`).
+    async fn publish(&self, event: Arc<Event>) {
+        let mut subscribers = self.subscribers.lock().await;
+        let mut dead = Vec::new();
+        for (pattern, senders) in subscribers.iter_mut() {
+            if !topic_matches(pattern, &event.topic) {
+                continue;
+            }
+            senders.retain(|(_, tx)| tx.send(event.clone()).is_ok());
+            if senders.is_empty() {
+                dead.push(pattern.clone());
+            }
+        }
+        for pattern in dead {
+            subscribers.remove(&pattern);
+        }
+    }
+}
+
+pub(crate) fn topic_matches(pattern: &str, topic: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => topic.starts_with(prefix),
+        None => pattern == topic,
+    }
+}
+
+pub struct Server<A> {
+    pub address: A,
+    shared: Arc<Shared>,
+    tls_acceptor: Option<TlsAcceptor>,
+}
+
+impl<A: ToSocketAddrs> Server<A> {
+    pub fn new(address: A) -> Server<A> {
         Server {
-            address: String::from(address),
+            address,
+            shared: Arc::new(Shared::new()),
+            tls_acceptor: None,
         }
     }
 
-    async fn read_event(socket: &mut TcpStream) -> Result<Event> {
-        let mut reader = BufReader::new(socket);
-        let mut buf = vec![0u8; 1024];
-        let bytes_read = reader.read_until(b'\n', &mut buf).await?;
-        let topic = std::str::from_utf8(&buf[..bytes_read])?
-            .trim_end_matches(&['\r', '\n'][..])
-            .to_string();
-        println!("Bytes Read: {} , Topic: {}", bytes_read, topic);
-        let mut length_bytes = [0u8; 4];
-        let _ = reader.read_exact(&mut length_bytes).await;
-        let length = u32::from_be_bytes(length_bytes);
-        let mut payload = vec![0u8; length as usize];
-        let _ = reader.read_exact(&mut payload).await?;
-        Ok(Event { topic, payload })
+    /// Accept TLS connections instead of plain TCP, terminating the handshake
+    /// with `acceptor` before handing the stream to the same event-parsing logic.
+    pub fn with_tls(mut self, acceptor: TlsAcceptor) -> Server<A> {
+        self.tls_acceptor = Some(acceptor);
+        self
+    }
+
+    async fn handle_connection<T: Transport>(shared: Arc<Shared>, transport: T) {
+        let (reader, writer) = split(transport);
+        let mut reader: FramedRead<ReadHalf<T>, EventCodec> =
+            FramedRead::new(reader, EventCodec::new());
+        let mut writer: FramedWrite<WriteHalf<T>, EventCodec> =
+            FramedWrite::new(writer, EventCodec::new());
+        let (tx, mut rx) = mpsc::unbounded_channel::<Arc<Event>>();
+
+        let write_task = spawn(async move {
+            while let Some(event) = rx.recv().await {
+                if let Err(err) = writer.send(event.as_ref()).await {
+                    error!("Error writing event to socket: {}", err);
+                    break;
+                }
+            }
+        });
+
+        // Forwarder tasks (and their registrations in `shared`) live only as
+        // long as this connection; both are torn down below on disconnect
+        // instead of lingering until an unrelated publish happens to prune them.
+        let mut subscriptions: Vec<(String, u64, tokio::task::JoinHandle<()>)> = Vec::new();
+
+        while let Some(frame) = reader.next().await {
+            match frame {
+                Ok(event) => match Frame::from(event) {
+                    Frame::Subscribe(topic) => {
+                        let (id, mut delivery) = shared.subscribe(topic.clone()).await;
+                        let tx = tx.clone();
+                        let handle = spawn(async move {
+                            while let Some(event) = delivery.recv().await {
+                                if tx.send(event).is_err() {
+                                    break;
+                                }
+                            }
+                        });
+                        subscriptions.push((topic, id, handle));
+                    }
+                    Frame::Publish(event) => {
+                        shared.publish(Arc::new(event)).await;
+                    }
+                },
+                Err(err) => {
+                    error!("Error reading frame from socket: {}", err);
+                    break;
+                }
+            }
+        }
+
+        for (topic, id, handle) in subscriptions {
+            handle.abort();
+            shared.unsubscribe(&topic, id).await;
+        }
+
+        drop(tx);
+        let _ = write_task.await;
     }
 
+    /// Binds to `self.address`, which may be a literal `SocketAddr`, a
+    /// `"host:port"` string, or a `(host, port)` tuple. Hostnames are resolved
+    /// via the OS resolver on Tokio's blocking pool; when resolution yields
+    /// several addresses, each is tried in turn until one binds.
+    ///
+    /// Runs with no connection limit and no way to stop short of an error;
+    /// see [`Server::listen_with_shutdown`] for bounded, stoppable serving.
     pub async fn listen(&self) -> Result<()> {
-        let listener = TcpListener::bind(String::from(&self.address)).await?;
-        info!("The server is running on {}", &self.address);
+        self.listen_with_shutdown(usize::MAX, &Notify::new()).await
+    }
+
+    /// Like [`Server::listen`], but bounds the number of concurrently open
+    /// connections to `max_connections` (acquiring a [`Semaphore`] permit per
+    /// connection) and stops accepting new connections as soon as `shutdown`
+    /// is notified, returning once every in-flight handler has finished.
+    pub async fn listen_with_shutdown(
+        &self,
+        max_connections: usize,
+        shutdown: &Notify,
+    ) -> Result<()> {
+        let listener = TcpListener::bind(&self.address).await?;
+        info!("The server is running on {}", listener.local_addr()?);
+        let semaphore = Arc::new(Semaphore::new(max_connections.min(Semaphore::MAX_PERMITS)));
+        let mut handlers = JoinSet::new();
+        let shutdown_signal = shutdown.notified();
+        tokio::pin!(shutdown_signal);
         loop {
-            let (mut socket, addr) = listener.accept().await?;
+            let permit = tokio::select! {
+                permit = semaphore.clone().acquire_owned() => permit?,
+                _ = &mut shutdown_signal => break,
+            };
+            let (socket, addr) = tokio::select! {
+                accepted = listener.accept() => accepted?,
+                _ = &mut shutdown_signal => break,
+            };
             info!("New connection from {}", addr);
-            spawn(async move {
-                loop {
-                    let event_result = Server::read_event(&mut socket).await;
-                    match event_result {
-                        Ok(event) => {
-                            println!("{}", event.topic);
-                        }
-                        Err(err) => {
-                            error!("Error reading event from sockect: {}", err);
-                            break;
+            let shared = self.shared.clone();
+            match self.tls_acceptor.clone() {
+                Some(acceptor) => {
+                    handlers.spawn(async move {
+                        match TlsTransport::accept(&acceptor, socket).await {
+                            Ok(transport) => Server::<A>::handle_connection(shared, transport).await,
+                            Err(err) => error!("TLS handshake with {} failed: {}", addr, err),
                         }
-                    }
+                        drop(permit);
+                    });
+                }
+                None => {
+                    handlers.spawn(async move {
+                        Server::<A>::handle_connection(shared, socket).await;
+                        drop(permit);
+                    });
                 }
-            });
+            }
         }
+        info!("Shutting down, waiting for {} connection(s) to drain", handlers.len());
+        while handlers.join_next().await.is_some() {}
+        Ok(())
     }
 }
@@ -0,0 +1,118 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::{TcpStream, ToSocketAddrs};
+use tokio_rustls::TlsAcceptor;
+use tracing::info;
+
+/// Any byte stream the server can read events from and write events to,
+/// whether it's a raw TCP socket, a TLS session, or (later) a Unix socket.
+pub trait Transport: AsyncRead + AsyncWrite + Send + Unpin {}
+
+impl<T: AsyncRead + AsyncWrite + Send + Unpin> Transport for T {}
+
+/// A transport that knows how to re-establish itself after the underlying
+/// connection drops, so a client doesn't have to hand-roll redial logic.
+#[async_trait]
+pub trait Reconnectable: Transport {
+    async fn reconnect(&mut self) -> Result<()>;
+}
+
+/// A plain TCP connection that remembers its peer so it can re-dial on failure.
+pub struct TcpTransport {
+    stream: TcpStream,
+    peer: SocketAddr,
+}
+
+impl TcpTransport {
+    pub async fn connect<A: ToSocketAddrs>(addr: A) -> Result<TcpTransport> {
+        let stream = TcpStream::connect(addr).await?;
+        let peer = stream.peer_addr()?;
+        Ok(TcpTransport { stream, peer })
+    }
+
+    pub fn from_stream(stream: TcpStream, peer: SocketAddr) -> TcpTransport {
+        TcpTransport { stream, peer }
+    }
+}
+
+impl AsyncRead for TcpTransport {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.stream).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for TcpTransport {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Pin::new(&mut self.stream).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.stream).poll_shutdown(cx)
+    }
+}
+
+#[async_trait]
+impl Reconnectable for TcpTransport {
+    async fn reconnect(&mut self) -> Result<()> {
+        info!("Reconnecting to {}", self.peer);
+        self.stream = TcpStream::connect(self.peer).await?;
+        Ok(())
+    }
+}
+
+/// A TLS session layered over another transport (typically a [`TcpTransport`]
+/// or raw `TcpStream`), accepted via `tokio-rustls`.
+pub struct TlsTransport<S> {
+    stream: tokio_rustls::server::TlsStream<S>,
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> TlsTransport<S> {
+    pub async fn accept(acceptor: &TlsAcceptor, stream: S) -> Result<TlsTransport<S>> {
+        let stream = acceptor.accept(stream).await?;
+        Ok(TlsTransport { stream })
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> AsyncRead for TlsTransport<S> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.stream).poll_read(cx, buf)
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> AsyncWrite for TlsTransport<S> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Pin::new(&mut self.stream).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.stream).poll_shutdown(cx)
+    }
+}
@@ -0,0 +1,41 @@
+use futures::StreamExt;
+use simplebus::{Client, Server};
+use tokio::time::{timeout, Duration};
+
+/// End-to-end: a client subscribes to a wildcard topic, publishes a matching
+/// event, and the subscription stream receives it — covers the codec,
+/// `Server`'s fan-out, and `Client`'s read/write split wiring together.
+#[tokio::test]
+async fn client_receives_events_published_to_a_matching_wildcard_topic() {
+    // `Server` doesn't expose its bound port before `listen` runs, so bind a
+    // throwaway listener first just to reserve a free one to connect to.
+    let probe = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = probe.local_addr().unwrap();
+    drop(probe);
+
+    let server = Server::new(addr);
+    let listen = tokio::spawn(async move { server.listen().await });
+
+    // Give the listener a moment to bind before connecting.
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let client = Client::connect(addr).await.expect("client connects");
+    let mut events = Box::pin(client.subscribe("orders.*"));
+
+    // Let the SUBSCRIBE frame reach the broker before publishing.
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    client
+        .publish("orders.created", b"hello".to_vec())
+        .expect("publish succeeds");
+
+    let event = timeout(Duration::from_secs(2), events.next())
+        .await
+        .expect("event arrives before timeout")
+        .expect("stream yields an event");
+
+    assert_eq!(event.topic, "orders.created");
+    assert_eq!(event.payload, b"hello");
+
+    listen.abort();
+}